@@ -7,10 +7,17 @@ use vecmath::{
     vec3_normalized_sub,
     vec3_cross,
     vec3_dot,
+    vec3_normalized,
+    vec3_square_len,
+    vec3_add,
+    vec3_sub,
+    vec3_len,
+    vec3_scale,
 };
+use vecmath::{col_mat4_transform, mat4_inv};
 use vecmath::col_mat4_mul as mul;
 use vecmath::traits::*;
-use quaternion::{Quaternion, rotate_vector};
+use quaternion::{self, Quaternion, rotate_vector};
 
 /// Computes a model view projection matrix.
 pub fn model_view_projection<T: Float + Copy>(
@@ -21,8 +28,77 @@ pub fn model_view_projection<T: Float + Copy>(
     mul(mul(projection, view), model)
 }
 
+/// Stores a precomputed view projection matrix,
+/// for computing model view projection matrices of many models.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ViewProjection<T=f32> {
+    /// The projection matrix multiplied with the view matrix.
+    pub matrix: Matrix4<T>,
+}
+
+impl<T: Float> ViewProjection<T> {
+    /// Constructs a new view projection from view and projection matrices.
+    pub fn new(view: Matrix4<T>, projection: Matrix4<T>) -> ViewProjection<T> {
+        ViewProjection {
+            matrix: mul(projection, view)
+        }
+    }
+
+    /// Computes a model view projection matrix.
+    ///
+    /// This is the same as `model_view_projection`,
+    /// but without multiplying the view and projection matrices again.
+    pub fn apply(&self, model: Matrix4<T>) -> Matrix4<T> {
+        mul(self.matrix, model)
+    }
+}
+
+/// Decomposes a view projection matrix into a camera and perspective settings.
+///
+/// The matrix is assumed to be the product of `CameraPerspective::projection`
+/// and `Camera::view`, which means a symmetric frustum
+/// and an orthonormal camera basis.
+/// Matrices with skew or an off-center frustum are not recovered correctly.
+pub fn decompose_view_projection<T: Float>(
+    vp: Matrix4<T>
+) -> (Camera<T>, CameraPerspective<T>)
+    where f64: Cast<T>
+{
+    let _1: T = One::one();
+    let pi: T = Radians::_180();
+    let _360: T = Cast::cast(360.0f64);
+    let row = |i: usize| [vp[0][i], vp[1][i], vp[2][i]];
+    let (sx, sy) = (vec3_len(row(0)), vec3_len(row(1)));
+    let right = vec3_scale(row(0), _1 / sx);
+    let up = vec3_scale(row(1), _1 / sy);
+    let forward = row(3);
+    // Distances of the camera position along each axis.
+    let (rp, up_p, fp) = (-vp[3][0] / sx, -vp[3][1] / sy, -vp[3][3]);
+    let position = vec3_add(
+        vec3_add(vec3_scale(right, rp), vec3_scale(up, up_p)),
+        vec3_scale(forward, fp)
+    );
+    let a = -vec3_dot(row(2), forward);
+    let b = vp[3][2] - a * fp;
+    let camera = Camera {
+        position: position,
+        up: up,
+        right: right,
+        forward: forward
+    };
+    let perspective = CameraPerspective {
+        fov: (_1 / sy).atan() * (_360 / pi),
+        near_clip: b / (a - _1),
+        far_clip: b / (a + _1),
+        aspect_ratio: sy / sx,
+    };
+    (camera, perspective)
+}
+
 /// Models a camera with position and directions.
 #[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Camera<T=f32> {
     /// The camera position.
     pub position: Vector3<T>,
@@ -36,6 +112,7 @@ pub struct Camera<T=f32> {
 
 /// Models camera perspective settings.
 #[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CameraPerspective<T=f32> {
     /// Field of view (in degrees).
     pub fov: T,
@@ -47,16 +124,38 @@ pub struct CameraPerspective<T=f32> {
     pub aspect_ratio: T,
 }
 
+/// Models camera orthographic settings.
+///
+/// Unlike `Camera::orthogonal`, which computes the view matrix,
+/// this describes an orthographic projection box in view space.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraOrthographic<T=f32> {
+    /// The left clip plane.
+    pub left: T,
+    /// The right clip plane.
+    pub right: T,
+    /// The bottom clip plane.
+    pub bottom: T,
+    /// The top clip plane.
+    pub top: T,
+    /// The near clip distance.
+    pub near_clip: T,
+    /// The far clip distance.
+    pub far_clip: T,
+}
+
 impl<T: Float + Copy> Camera<T> {
     /// Constructs a new camera.
     ///
     /// Places the camera at [x, y, z], looking towards pozitive z.
+    /// The right direction is negative x, since the coordinate system is right-handed.
     pub fn new(position: Vector3<T>) -> Camera<T> {
         let _0 = Zero::zero();
-        let _1 = One::one();
+        let _1: T = One::one();
         Camera {
             position: position,
-            right:   [_1, _0, _0],
+            right:   [-_1, _0, _0],
             up:      [_0, _1, _0],
             forward: [_0, _0, _1]
         }
@@ -64,33 +163,76 @@ impl<T: Float + Copy> Camera<T> {
 
     /// Computes an orthogonal matrix for the camera.
     ///
-    /// This matrix can be used to transform coordinates to the screen.
+    /// This is the same as `view`, which is preferred.
     pub fn orthogonal(&self) -> Matrix4<T> {
+        self.view()
+    }
+
+    /// Computes the view matrix for the camera.
+    ///
+    /// This matrix can be used to transform coordinates to the screen.
+    /// The forward direction is mapped to negative z,
+    /// which is where `CameraPerspective::projection` expects it.
+    pub fn view(&self) -> Matrix4<T> {
         let p = self.position;
         let r = self.right;
         let u = self.up;
         let f = self.forward;
         let _0 = Zero::zero();
         [
-            [r[0], u[0], f[0], _0],
-            [r[1], u[1], f[1], _0],
-            [r[2], u[2], f[2], _0],
-            [-vec3_dot(r, p), -vec3_dot(u, p), -vec3_dot(f, p), One::one()]
+            [r[0], u[0], -f[0], _0],
+            [r[1], u[1], -f[1], _0],
+            [r[2], u[2], -f[2], _0],
+            [-vec3_dot(r, p), -vec3_dot(u, p), vec3_dot(f, p), One::one()]
         ]
     }
 
     /// Orients the camera to look at a point.
+    ///
+    /// The forward direction points from the camera position towards the point.
+    /// The up direction is adjusted to stay orthogonal to the forward direction.
+    /// When looking along the up direction, such as straight down from above,
+    /// the previous forward direction is used as reference for up instead.
     pub fn look_at(&mut self, point: Vector3<T>) {
-        self.forward = vec3_normalized_sub(point, self.position);
-        self.update_right();
+        let _0: T = Zero::zero();
+        let _1: T = One::one();
+        let epsilon = T::from_f64(1e-10);
+        let forward = vec3_normalized_sub(point, self.position);
+        let candidates = [self.up, self.forward, [_0, _0, _1], [_1, _0, _0]];
+        let right = candidates.iter()
+            .map(|&up| vec3_cross(forward, up))
+            .find(|&right| vec3_square_len(right) > epsilon)
+            .unwrap_or(self.right);
+        self.forward = forward;
+        self.right = vec3_normalized(right);
+        self.up = vec3_cross(self.right, self.forward);
     }
 
     /// Sets yaw and pitch angle of camera in radians.
     pub fn set_yaw_pitch(&mut self, yaw: T, pitch: T) {
+        self.set_yaw_pitch_roll(yaw, pitch, Zero::zero());
+    }
+
+    /// Sets yaw, pitch and roll angle of camera in radians.
+    ///
+    /// The roll rotates the up and right directions around the forward direction.
+    pub fn set_yaw_pitch_roll(&mut self, yaw: T, pitch: T, roll: T) {
         let (y_s, y_c, p_s, p_c) = (yaw.sin(), yaw.cos(), pitch.sin(), pitch.cos());
         self.forward = [y_s * p_c, p_s, y_c * p_c];
         self.up = [y_s * -p_s, p_c, y_c * -p_s];
         self.update_right();
+        let (r_s, r_c) = (roll.sin(), roll.cos());
+        let (up, right) = (self.up, self.right);
+        self.up = vec3_add(vec3_scale(up, r_c), vec3_scale(right, r_s));
+        self.right = vec3_sub(vec3_scale(right, r_c), vec3_scale(up, r_s));
+    }
+
+    /// Computes yaw and pitch angle of camera in radians.
+    ///
+    /// This is the inverse of `set_yaw_pitch`.
+    /// When looking straight up or down the yaw is zero.
+    pub fn yaw_pitch(&self) -> (T, T) {
+        yaw_pitch_of(self.forward)
     }
 
     /// Sets forward, up, and right vectors from a Quaternion rotation
@@ -106,11 +248,161 @@ impl<T: Float + Copy> Camera<T> {
         self.update_right();
     }
 
+    /// Computes the yaw and pitch in radians the camera is turned
+    /// relative to a reference forward direction, such as a body.
+    ///
+    /// Uses the same angle convention as `set_yaw_pitch`.
+    /// The yaw is clamped to `[-max_yaw, max_yaw]`
+    /// and the pitch to `[-max_pitch, max_pitch]`.
+    pub fn aim_offset(
+        &self,
+        reference_forward: Vector3<T>,
+        max_yaw: T,
+        max_pitch: T
+    ) -> (T, T) {
+        let _1: T = One::one();
+        let _2: T = _1 + _1;
+        let pi: T = Radians::_180();
+        let (yaw, pitch) = yaw_pitch_of(self.forward);
+        let (ref_yaw, ref_pitch) = yaw_pitch_of(vec3_normalized(reference_forward));
+        let mut d_yaw = yaw - ref_yaw;
+        if d_yaw > pi { d_yaw -= _2 * pi; }
+        if d_yaw < -pi { d_yaw += _2 * pi; }
+        let d_pitch = pitch - ref_pitch;
+        (
+            d_yaw.min(max_yaw).max(-max_yaw),
+            d_pitch.min(max_pitch).max(-max_pitch)
+        )
+    }
+
+    /// Computes a world space ray through a point on the screen.
+    ///
+    /// The point is in normalized device coordinates,
+    /// where `[0, 0]` is the center of the screen.
+    /// Returns the ray origin on the near clip plane and the normalized direction.
+    pub fn pick_ray(
+        &self,
+        perspective: &CameraPerspective<T>,
+        ndc: [T; 2]
+    ) -> (Vector3<T>, Vector3<T>)
+        where f64: Cast<T>
+    {
+        let _1: T = One::one();
+        let inv = mat4_inv(perspective.view_projection(self));
+        let unproject = |z: T| {
+            let p = col_mat4_transform(inv, [ndc[0], ndc[1], z, _1]);
+            [p[0] / p[3], p[1] / p[3], p[2] / p[3]]
+        };
+        let near = unproject(-_1);
+        let far = unproject(_1);
+        (near, vec3_normalized_sub(far, near))
+    }
+
+    /// Computes the corners of the visible region on a horizontal ground plane.
+    ///
+    /// Intersects the rays through the corners of the screen with the plane
+    /// at height `ground_y`, in the order bottom-left, bottom-right,
+    /// top-right and top-left.
+    /// Returns `None` if any of the rays does not hit the plane in front of the camera.
+    pub fn ground_footprint(
+        &self,
+        perspective: &CameraPerspective<T>,
+        ground_y: T
+    ) -> Option<[Vector3<T>; 4]>
+        where f64: Cast<T>
+    {
+        let _0: T = Zero::zero();
+        let _1: T = One::one();
+        let hit = |ndc: [T; 2]| {
+            let (origin, dir) = self.pick_ray(perspective, ndc);
+            if dir[1] == _0 { return None; }
+            let t = (ground_y - origin[1]) / dir[1];
+            // Also rejects NaN.
+            if t > _0 { Some(vec3_add(origin, vec3_scale(dir, t))) } else { None }
+        };
+        Some([
+            hit([-_1, -_1])?,
+            hit([_1, -_1])?,
+            hit([_1, _1])?,
+            hit([-_1, _1])?,
+        ])
+    }
+
+    /// Interpolates between two cameras, where `t` is clamped to `[0, 1]`.
+    ///
+    /// The position is interpolated linearly
+    /// and the orientation by spherical linear interpolation.
+    pub fn interpolate(&self, other: &Camera<T>, t: T) -> Camera<T>
+        where f64: Cast<T>
+    {
+        let _0: T = Zero::zero();
+        let _1: T = One::one();
+        let t = t.min(_1).max(_0);
+        let a = self.rotation();
+        let mut b = other.rotation();
+        let mut cos_theta = quaternion::dot(a, b);
+        // Take the shortest path.
+        if cos_theta < _0 {
+            b = quaternion::scale(b, -_1);
+            cos_theta = -cos_theta;
+        }
+        let (wa, wb) = if cos_theta > Cast::cast(0.9995f64) {
+            (_1 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (((_1 - t) * theta).sin() / sin_theta, (t * theta).sin() / sin_theta)
+        };
+        let q = quaternion::add(quaternion::scale(a, wa), quaternion::scale(b, wb));
+        let q = quaternion::scale(q, _1 / quaternion::len(q));
+        let mut camera = Camera::new(vec3_add(
+            vec3_scale(self.position, _1 - t),
+            vec3_scale(other.position, t)
+        ));
+        camera.set_rotation(q);
+        camera
+    }
+
+    /// Computes the rotation relative to the positive z-axis,
+    /// which is the inverse of `set_rotation`.
+    fn rotation(&self) -> Quaternion<T> {
+        let _1: T = One::one();
+        let _2: T = _1 + _1;
+        let _4: T = _2 + _2;
+        let (r, u, f) = (self.right, self.up, self.forward);
+        // Rotation matrix with columns -right, up and forward.
+        let (m00, m01, m02) = (-r[0], u[0], f[0]);
+        let (m10, m11, m12) = (-r[1], u[1], f[1]);
+        let (m20, m21, m22) = (-r[2], u[2], f[2]);
+        let trace = m00 + m11 + m22;
+        if trace > Zero::zero() {
+            let s = (trace + _1).sqrt() * _2;
+            (s / _4, [(m21 - m12) / s, (m02 - m20) / s, (m10 - m01) / s])
+        } else if m00 > m11 && m00 > m22 {
+            let s = (_1 + m00 - m11 - m22).sqrt() * _2;
+            ((m21 - m12) / s, [s / _4, (m01 + m10) / s, (m02 + m20) / s])
+        } else if m11 > m22 {
+            let s = (_1 + m11 - m00 - m22).sqrt() * _2;
+            ((m02 - m20) / s, [(m01 + m10) / s, s / _4, (m12 + m21) / s])
+        } else {
+            let s = (_1 + m22 - m00 - m11).sqrt() * _2;
+            ((m10 - m01) / s, [(m02 + m20) / s, (m12 + m21) / s, s / _4])
+        }
+    }
+
     fn update_right(&mut self) {
-        self.right = vec3_cross(self.up, self.forward);
+        self.right = vec3_cross(self.forward, self.up);
     }
 }
 
+/// Computes yaw and pitch in radians of a normalized direction.
+fn yaw_pitch_of<T: Float>(dir: Vector3<T>) -> (T, T) {
+    let _0: T = Zero::zero();
+    let _1: T = One::one();
+    let yaw = if dir[0] == _0 && dir[2] == _0 { _0 } else { dir[0].atan2(dir[2]) };
+    (yaw, dir[1].min(_1).max(-_1).asin())
+}
+
 impl<T: Float> CameraPerspective<T>
     where f64: Cast<T>
 {
@@ -130,4 +422,675 @@ impl<T: Float> CameraPerspective<T>
             [_0, _0, (_2 * far * near) / (near - far), _0]
         ]
     }
+
+    /// Computes a view projection matrix for geometry in world space.
+    ///
+    /// This is the same as `model_view_projection` with an identity model matrix.
+    pub fn view_projection(&self, camera: &Camera<T>) -> Matrix4<T> {
+        mul(self.projection(), camera.view())
+    }
+
+    /// Computes a projection matrix with reversed depth and an infinite far plane.
+    ///
+    /// The near clip plane maps to depth 1 and depth approaches 0 towards infinity,
+    /// which gives better depth precision with a `[0, 1]` depth range
+    /// and a `GREATER` depth test.
+    /// The far clip distance is ignored.
+    pub fn projection_infinite_reverse_z(&self) -> Matrix4<T> {
+        let _0: T = Zero::zero();
+        let _1: T = One::one();
+        let pi: T = Radians::_180();
+        let _360: T = Cast::cast(360.0f64);
+        let f = _1 / (self.fov * (pi / _360)).tan();
+        [
+            [f / self.aspect_ratio, _0, _0, _0],
+            [_0, f, _0, _0],
+            [_0, _0, _0, -_1],
+            [_0, _0, self.near_clip, _0]
+        ]
+    }
+
+    /// Computes the distance at which one world unit covers
+    /// the given number of pixels vertically.
+    ///
+    /// The viewport height is in pixels.
+    pub fn distance_for_pixels_per_unit(
+        &self,
+        pixels_per_unit: T,
+        viewport_height: T
+    ) -> T {
+        let _1: T = One::one();
+        let _2: T = _1 + _1;
+        let pi: T = Radians::_180();
+        let _360: T = Cast::cast(360.0f64);
+        let half_height = (self.fov * (pi / _360)).tan();
+        viewport_height / (pixels_per_unit * _2 * half_height)
+    }
+}
+
+impl<T: Float> CameraOrthographic<T> {
+    /// Computes a projection matrix for the orthographic settings.
+    pub fn projection(&self) -> Matrix4<T> {
+        let _0: T = Zero::zero();
+        let _1: T = One::one();
+        let _2: T = _1 + _1;
+        let (l, r) = (self.left, self.right);
+        let (b, t) = (self.bottom, self.top);
+        let (far, near) = (self.far_clip, self.near_clip);
+        [
+            [_2 / (r - l), _0, _0, _0],
+            [_0, _2 / (t - b), _0, _0],
+            [_0, _0, _2 / (near - far), _0],
+            [(r + l) / (l - r), (t + b) / (b - t), (far + near) / (near - far), _1]
+        ]
+    }
+}
+
+/// Computes the orthographic box height at which one world unit covers
+/// the given number of pixels vertically.
+///
+/// The viewport height is in pixels.
+pub fn orthographic_height_for_pixels_per_unit<T: Float>(
+    pixels_per_unit: T,
+    viewport_height: T
+) -> T {
+    viewport_height / pixels_per_unit
+}
+
+/// Eases the field of view from one value to another over time.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FovTween<T=f32> {
+    /// The field of view at the start (in degrees).
+    pub from: T,
+    /// The field of view at the end (in degrees).
+    pub to: T,
+    /// The duration of the transition in seconds.
+    pub duration: T,
+    /// The time elapsed since the start in seconds.
+    pub elapsed: T,
+}
+
+impl<T: Float> FovTween<T> {
+    /// Constructs a new transition starting at zero elapsed time.
+    pub fn new(from: T, to: T, duration: T) -> FovTween<T> {
+        FovTween {
+            from: from,
+            to: to,
+            duration: duration,
+            elapsed: Zero::zero(),
+        }
+    }
+
+    /// Advances the transition and returns the current field of view.
+    ///
+    /// Negative time steps are ignored.
+    pub fn update(&mut self, dt: T) -> T {
+        let _0: T = Zero::zero();
+        self.elapsed = (self.elapsed + dt.max(_0)).min(self.duration);
+        self.fov()
+    }
+
+    /// Computes the current field of view using smoothstep easing.
+    pub fn fov(&self) -> T {
+        let _1: T = One::one();
+        let _2: T = _1 + _1;
+        let _3: T = _2 + _1;
+        if self.is_complete() { return self.to; }
+        let t = self.elapsed / self.duration;
+        let t = t * t * (_3 - _2 * t);
+        self.from + (self.to - self.from) * t
+    }
+
+    /// Returns `true` when the transition has reached the end.
+    pub fn is_complete(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+/// Models the view frustum as six planes, used for culling.
+///
+/// Each plane is stored as `[a, b, c, d]` with a normalized normal `[a, b, c]`
+/// pointing into the frustum, such that `a * x + b * y + c * z + d`
+/// is the signed distance of a point from the plane.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frustum<T=f32> {
+    /// The left, right, bottom, top, near and far planes.
+    pub planes: [[T; 4]; 6],
+}
+
+impl<T: Float> Frustum<T> {
+    /// Constructs a frustum from a camera and perspective settings.
+    pub fn new(camera: &Camera<T>, perspective: &CameraPerspective<T>) -> Frustum<T>
+        where f64: Cast<T>
+    {
+        Frustum::from_view_projection(perspective.view_projection(camera))
+    }
+
+    /// Extracts the frustum planes from a view projection matrix.
+    ///
+    /// Uses the Gribb/Hartmann method.
+    pub fn from_view_projection(vp: Matrix4<T>) -> Frustum<T> {
+        let _1: T = One::one();
+        let row = |i: usize| [vp[0][i], vp[1][i], vp[2][i], vp[3][i]];
+        let w = row(3);
+        let plane = |r: [T; 4], sign: T| {
+            let p = [w[0] + sign * r[0], w[1] + sign * r[1], w[2] + sign * r[2], w[3] + sign * r[3]];
+            let inv_len = _1 / vec3_len([p[0], p[1], p[2]]);
+            [p[0] * inv_len, p[1] * inv_len, p[2] * inv_len, p[3] * inv_len]
+        };
+        Frustum {
+            planes: [
+                plane(row(0), _1),
+                plane(row(0), -_1),
+                plane(row(1), _1),
+                plane(row(1), -_1),
+                plane(row(2), _1),
+                plane(row(2), -_1),
+            ]
+        }
+    }
+
+    /// Returns `true` if the point is inside the frustum.
+    pub fn contains_point(&self, point: Vector3<T>) -> bool {
+        let _0: T = Zero::zero();
+        self.planes.iter().all(|p| vec3_dot([p[0], p[1], p[2]], point) + p[3] >= _0)
+    }
+
+    /// Returns `true` if the sphere is inside or intersects the frustum.
+    pub fn intersects_sphere(&self, center: Vector3<T>, radius: T) -> bool {
+        self.planes.iter().all(|p| vec3_dot([p[0], p[1], p[2]], center) + p[3] >= -radius)
+    }
+}
+
+/// Eases the field of view towards a target, for aiming or zoom effects.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FovController<T=f32> {
+    /// The current field of view (in degrees).
+    pub fov: T,
+    /// The target field of view (in degrees).
+    pub target: T,
+    /// The rate at which the remaining difference decays.
+    ///
+    /// The remaining difference shrinks by a factor of `e^(-speed)` per second,
+    /// independently of the frame rate.
+    pub speed: T,
+}
+
+impl<T: Float> FovController<T>
+    where f64: Cast<T>
+{
+    /// Constructs a new controller at rest at the given field of view.
+    pub fn new(fov: T, speed: T) -> FovController<T> {
+        FovController {
+            fov: fov,
+            target: fov,
+            speed: speed,
+        }
+    }
+
+    /// Moves the field of view towards the target.
+    ///
+    /// Never overshoots the target, even for large time steps.
+    /// Negative time steps are ignored.
+    pub fn update(&mut self, dt: T) {
+        let _0: T = Zero::zero();
+        let _1: T = One::one();
+        let e: T = Cast::cast(::std::f64::consts::E);
+        let t = _1 - e.powf(-self.speed * dt.max(_0));
+        self.fov = self.fov + (self.target - self.fov) * t;
+    }
+
+    /// Computes perspective settings with the current field of view.
+    pub fn perspective(&self, perspective: &CameraPerspective<T>) -> CameraPerspective<T> {
+        CameraPerspective {
+            fov: self.fov,
+            ..perspective.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vecmath::{mat4_id, vec3_neg};
+
+    const EPSILON: f64 = 1e-6;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < EPSILON, "{} != {}", a, b);
+    }
+
+    fn assert_vec3_close(a: Vector3<f64>, b: Vector3<f64>) {
+        for i in 0..3 { assert_close(a[i], b[i]); }
+    }
+
+    fn assert_mat4_close(a: Matrix4<f64>, b: Matrix4<f64>) {
+        for i in 0..4 { for j in 0..4 { assert_close(a[i][j], b[i][j]); } }
+    }
+
+    fn perspective() -> CameraPerspective<f64> {
+        CameraPerspective {
+            fov: 70.0,
+            near_clip: 0.2,
+            far_clip: 500.0,
+            aspect_ratio: 1.6,
+        }
+    }
+
+    #[test]
+    fn decompose_view_projection_round_trip() {
+        let mut camera = Camera::new([1.0, 2.0, 3.0]);
+        camera.set_yaw_pitch(0.7, -0.3);
+        let perspective = perspective();
+        let vp = model_view_projection(mat4_id(), camera.orthogonal(), perspective.projection());
+        let (c, p) = decompose_view_projection(vp);
+        assert_vec3_close(c.position, camera.position);
+        assert_vec3_close(c.forward, camera.forward);
+        assert_vec3_close(c.up, camera.up);
+        assert_vec3_close(c.right, camera.right);
+        assert_close(p.fov, perspective.fov);
+        assert_close(p.aspect_ratio, perspective.aspect_ratio);
+        assert_close(p.near_clip, perspective.near_clip);
+        assert!((p.far_clip - perspective.far_clip).abs() < 1e-6 * perspective.far_clip);
+        assert_mat4_close(model_view_projection(mat4_id(), c.orthogonal(), p.projection()), vp);
+    }
+
+    #[test]
+    fn orthographic_projection() {
+        let ortho = CameraOrthographic {
+            left: -2.0, right: 6.0,
+            bottom: 1.0, top: 3.0,
+            near_clip: 1.0, far_clip: 11.0
+        };
+        let m = ortho.projection();
+        let center = col_mat4_transform(m, [2.0, 2.0, -6.0, 1.0]);
+        assert_close(center[0], 0.0);
+        assert_close(center[1], 0.0);
+        assert_close(col_mat4_transform(m, [2.0, 2.0, -1.0, 1.0])[2], -1.0);
+        assert_close(col_mat4_transform(m, [2.0, 2.0, -11.0, 1.0])[2], 1.0);
+    }
+
+    #[test]
+    fn look_at_faces_target() {
+        let mut camera = Camera::new([0.0, 0.0, -5.0]);
+        camera.set_yaw_pitch(2.0, 0.5);
+        let target = [1.0, 2.0, 3.0];
+        camera.look_at(target);
+        assert!(vec3_dot(camera.forward, vec3_sub(target, camera.position)) > 0.0);
+        assert_close(vec3_dot(camera.forward, camera.up), 0.0);
+        assert_close(vec3_dot(camera.forward, camera.right), 0.0);
+        assert_close(vec3_len(camera.up), 1.0);
+        assert_close(vec3_len(camera.right), 1.0);
+    }
+
+    #[test]
+    fn look_at_straight_down() {
+        let mut camera = Camera::new([0.0, 10.0, 0.0]);
+        camera.look_at([0.0, 0.0, 0.0]);
+        assert_vec3_close(camera.forward, [0.0, -1.0, 0.0]);
+        assert_vec3_close(camera.up, [0.0, 0.0, 1.0]);
+        assert_vec3_close(camera.right, [-1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn view_puts_target_in_front() {
+        let mut camera = Camera::new([0.0, 0.0, -5.0]);
+        camera.look_at([0.0, 0.0, 0.0]);
+        let vp = model_view_projection(mat4_id(), camera.orthogonal(), perspective().projection());
+        let clip = col_mat4_transform(vp, [0.0, 0.0, 0.0, 1.0]);
+        assert!(clip[3] > 0.0);
+        for i in 0..3 { assert!(clip[i].abs() <= clip[3]); }
+        // The camera's right direction is on the right side of the screen.
+        let clip = col_mat4_transform(vp, [-1.0, 0.0, 0.0, 1.0]);
+        assert!(clip[0] / clip[3] > 0.0);
+    }
+
+    #[test]
+    fn pick_ray_center_along_forward() {
+        let mut camera = Camera::new([1.0, 2.0, -5.0]);
+        camera.set_yaw_pitch(0.5, 0.2);
+        let (origin, dir) = camera.pick_ray(&perspective(), [0.0, 0.0]);
+        assert_vec3_close(dir, camera.forward);
+        let near = vec3_add(camera.position, vec3_scale(camera.forward, perspective().near_clip));
+        assert_vec3_close(origin, near);
+    }
+
+    #[test]
+    fn ground_footprint_looking_down() {
+        let mut camera = Camera::new([0.0, 10.0, 0.0]);
+        camera.look_at([0.0, 0.0, 0.0]);
+        let corners = camera.ground_footprint(&perspective(), 0.0).unwrap();
+        for c in &corners {
+            assert_close(c[1], 0.0);
+            assert!(c.iter().all(|x| x.is_finite()));
+        }
+    }
+
+    #[test]
+    fn ground_footprint_horizon_and_sky() {
+        let mut camera = Camera::new([0.0, 10.0, 0.0]);
+        camera.look_at([0.0, 10.0, 10.0]);
+        assert_eq!(camera.ground_footprint(&perspective(), 0.0), None);
+        camera.look_at([0.0, 20.0, 10.0]);
+        assert_eq!(camera.ground_footprint(&perspective(), 0.0), None);
+    }
+
+    #[test]
+    fn ground_footprint_corner_order() {
+        let mut camera = Camera::new([0.0, 10.0, 0.0]);
+        camera.look_at([0.0, 0.0, 10.0]);
+        let corners = camera.ground_footprint(&perspective(), 0.0).unwrap();
+        let side = |c: Vector3<f64>| vec3_dot(vec3_sub(c, camera.position), camera.right);
+        let depth = |c: Vector3<f64>| vec3_dot(vec3_sub(c, camera.position), camera.forward);
+        // Bottom-left, bottom-right, top-right, top-left.
+        assert!(side(corners[0]) < 0.0);
+        assert!(side(corners[1]) > 0.0);
+        assert!(side(corners[2]) > 0.0);
+        assert!(side(corners[3]) < 0.0);
+        assert!(depth(corners[0]) < depth(corners[3]));
+        assert!(depth(corners[1]) < depth(corners[2]));
+    }
+
+    #[test]
+    fn yaw_pitch_round_trip() {
+        let mut camera = Camera::new([0.0, 0.0, 0.0]);
+        for &(yaw, pitch) in &[(0.0, 0.0), (-2.5, 0.7), (1.2, -1.1), (3.0, 0.3)] {
+            camera.set_yaw_pitch(yaw, pitch);
+            let (y, p) = camera.yaw_pitch();
+            assert_close(y, yaw);
+            assert_close(p, pitch);
+        }
+    }
+
+    #[test]
+    fn yaw_pitch_at_poles() {
+        use std::f64::consts::FRAC_PI_2;
+
+        let mut camera = Camera::new([0.0, 0.0, 0.0]);
+        for &pitch in &[FRAC_PI_2, -FRAC_PI_2] {
+            camera.set_yaw_pitch(1.0, pitch);
+            let (y, p) = camera.yaw_pitch();
+            assert!(y.is_finite());
+            assert_close(p, pitch);
+        }
+        camera.forward = [0.0, 1.0, 0.0];
+        assert_eq!(camera.yaw_pitch().0, 0.0);
+    }
+
+    #[test]
+    fn interpolate_halfway() {
+        use std::f64::consts::FRAC_PI_4;
+
+        let a = Camera::new([0.0, 0.0, 0.0]);
+        let mut b = Camera::new([2.0, 0.0, 0.0]);
+        b.look_at([3.0, 0.0, 0.0]);
+        let c = a.interpolate(&b, 0.5);
+        assert_vec3_close(c.position, [1.0, 0.0, 0.0]);
+        assert_close(vec3_dot(c.forward, a.forward).acos(), FRAC_PI_4);
+        assert_close(vec3_dot(c.forward, b.forward).acos(), FRAC_PI_4);
+        assert_vec3_close(c.up, [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn interpolate_clamps_t() {
+        let mut a = Camera::new([0.0, 0.0, 0.0]);
+        a.set_yaw_pitch(-2.0, 0.4);
+        let mut b = Camera::new([2.0, 0.0, 0.0]);
+        b.set_yaw_pitch(2.9, -1.2);
+        for &(t, expected) in &[(-1.0, &a), (0.0, &a), (1.0, &b), (7.0, &b)] {
+            let c = a.interpolate(&b, t);
+            assert_vec3_close(c.position, expected.position);
+            assert_vec3_close(c.forward, expected.forward);
+            assert_vec3_close(c.up, expected.up);
+            assert_vec3_close(c.right, expected.right);
+        }
+    }
+
+    #[test]
+    fn frustum_spheres_at_planes() {
+        let mut camera = Camera::new([0.0, 0.0, 0.0]);
+        camera.look_at([0.0, 0.0, 5.0]);
+        let perspective = CameraPerspective {
+            fov: 90.0,
+            near_clip: 1.0,
+            far_clip: 100.0,
+            aspect_ratio: 1.0,
+        };
+        let frustum = Frustum::new(&camera, &perspective);
+        let (r, d) = (0.5, 0.01);
+        // Sphere centers are just outside each plane, by a bit less
+        // or a bit more than the radius.
+        // With a 90 degree field of view, the side planes pass through
+        // x = +-z and y = +-z, so moving sqrt(2) along an axis
+        // moves one unit away from the plane.
+        let s = 2.0f64.sqrt();
+        let side = |inside: bool| if inside { 10.0 + (r - d) * s } else { 10.0 + (r + d) * s };
+        for &inside in &[true, false] {
+            let centers = [
+                [side(inside), 0.0, 10.0],
+                [-side(inside), 0.0, 10.0],
+                [0.0, side(inside), 10.0],
+                [0.0, -side(inside), 10.0],
+                [0.0, 0.0, if inside { 1.0 - r + d } else { 1.0 - r - d }],
+                [0.0, 0.0, if inside { 100.0 + r - d } else { 100.0 + r + d }],
+            ];
+            for &c in &centers {
+                assert_eq!(frustum.intersects_sphere(c, r), inside, "{:?}", c);
+            }
+        }
+        assert!(frustum.contains_point([0.0, 0.0, 5.0]));
+        assert!(!frustum.contains_point([0.0, 0.0, -5.0]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut camera = Camera::new([1.0, 2.0, 3.0]);
+        camera.set_yaw_pitch(0.3, 0.1);
+        let json = ::serde_json::to_string(&camera).unwrap();
+        let camera2: Camera<f64> = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(camera, camera2);
+
+        let perspective = perspective();
+        let json = ::serde_json::to_string(&perspective).unwrap();
+        let perspective2: CameraPerspective<f64> = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(perspective, perspective2);
+    }
+
+    #[test]
+    fn roll_swaps_up_and_right() {
+        use std::f64::consts::FRAC_PI_2;
+
+        let mut camera = Camera::new([0.0, 0.0, 0.0]);
+        camera.set_yaw_pitch(0.3, 0.1);
+        let before = camera.clone();
+        camera.set_yaw_pitch_roll(0.3, 0.1, FRAC_PI_2);
+        assert_vec3_close(camera.forward, before.forward);
+        assert_vec3_close(camera.up, before.right);
+        assert_vec3_close(camera.right, vec3_neg(before.up));
+
+        camera.set_yaw_pitch_roll(0.3, 0.1, 0.4);
+        let (f, u, r) = (camera.forward, camera.up, camera.right);
+        assert_close(vec3_len(f), 1.0);
+        assert_close(vec3_len(u), 1.0);
+        assert_close(vec3_len(r), 1.0);
+        assert_close(vec3_dot(f, u), 0.0);
+        assert_close(vec3_dot(f, r), 0.0);
+        assert_close(vec3_dot(u, r), 0.0);
+    }
+
+    #[test]
+    fn infinite_reverse_z_depth() {
+        let perspective = perspective();
+        let m = perspective.projection_infinite_reverse_z();
+        let depth = |z: f64| {
+            let clip = col_mat4_transform(m, [0.0, 0.0, z, 1.0]);
+            clip[2] / clip[3]
+        };
+        assert_close(depth(-perspective.near_clip), 1.0);
+        assert!(depth(-1e3) < depth(-1.0));
+        assert!(depth(-1e9) < 1e-6);
+        assert!(depth(-1e9) > 0.0);
+    }
+
+    #[test]
+    fn view_projection_matches_model_view_projection() {
+        let mut camera = Camera::new([1.0, 2.0, 3.0]);
+        camera.set_yaw_pitch(0.3, 0.1);
+        let perspective = perspective();
+        assert_eq!(camera.view(), camera.orthogonal());
+        assert_mat4_close(
+            perspective.view_projection(&camera),
+            model_view_projection(mat4_id(), camera.view(), perspective.projection())
+        );
+    }
+
+    #[test]
+    fn fov_controller_approaches_target() {
+        let mut controller = FovController::new(90.0, 8.0);
+        controller.target = 30.0;
+        let mut previous = controller.fov;
+        for _ in 0..60 {
+            controller.update(1.0 / 30.0);
+            assert!(controller.fov < previous);
+            assert!(controller.fov >= controller.target);
+            previous = controller.fov;
+        }
+        assert!(controller.fov - controller.target < 0.1);
+        controller.update(100.0);
+        assert_close(controller.fov, controller.target);
+    }
+
+    #[test]
+    fn fov_controller_frame_rate_independent() {
+        let mut a = FovController::new(90.0, 1.0);
+        let mut b = a.clone();
+        a.target = 0.0;
+        b.target = 0.0;
+        a.update(0.5);
+        a.update(0.5);
+        b.update(1.0);
+        assert_close(a.fov, b.fov);
+    }
+
+    #[test]
+    fn view_projection_apply() {
+        let mut camera = Camera::new([1.0, 2.0, 3.0]);
+        camera.set_yaw_pitch(0.3, 0.1);
+        let (view, projection) = (camera.view(), perspective().projection());
+        let vp = ViewProjection::new(view, projection);
+        let models = [
+            mat4_id(),
+            [[1.0, 0.2, 0.0, 0.0], [0.0, 2.0, 0.0, 0.0], [0.3, 0.0, 1.0, 0.0], [4.0, 5.0, 6.0, 1.0]],
+            [[0.0, 0.0, -1.0, 0.0], [0.0, 1.0, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0], [-7.0, 0.5, 2.0, 1.0]],
+            [[3.0, 0.0, 0.0, 0.0], [0.0, 3.0, 0.0, 0.0], [0.0, 0.0, 3.0, 0.0], [0.0, -1.0, 0.0, 1.0]],
+        ];
+        for &model in &models {
+            assert_mat4_close(vp.apply(model), model_view_projection(model, view, projection));
+        }
+    }
+
+    #[test]
+    fn distance_for_pixels_per_unit() {
+        let perspective = CameraPerspective {
+            fov: 90.0,
+            near_clip: 0.1,
+            far_clip: 100.0,
+            aspect_ratio: 1.0,
+        };
+        let d = perspective.distance_for_pixels_per_unit(10.0, 600.0);
+        assert_close(d, 30.0);
+        let vp = perspective.view_projection(&Camera::new([0.0, 0.0, 0.0]));
+        let a = col_mat4_transform(vp, [0.0, 0.0, d, 1.0]);
+        let b = col_mat4_transform(vp, [0.0, 1.0, d, 1.0]);
+        assert_close((b[1] / b[3] - a[1] / a[3]) * 600.0 / 2.0, 10.0);
+    }
+
+    #[test]
+    fn orthographic_height_for_pixels() {
+        let h = orthographic_height_for_pixels_per_unit(10.0, 600.0);
+        assert_close(h, 60.0);
+        let ortho = CameraOrthographic {
+            left: -40.0, right: 40.0,
+            bottom: -h / 2.0, top: h / 2.0,
+            near_clip: 0.1, far_clip: 100.0
+        };
+        let m = ortho.projection();
+        let a = col_mat4_transform(m, [0.0, 0.0, -5.0, 1.0]);
+        let b = col_mat4_transform(m, [0.0, 1.0, -5.0, 1.0]);
+        assert_close((b[1] - a[1]) * 600.0 / 2.0, 10.0);
+    }
+
+    #[test]
+    fn aim_offset_plain() {
+        let mut camera = Camera::new([0.0, 0.0, 0.0]);
+        camera.set_yaw_pitch(0.4, 0.2);
+        let (yaw, pitch) = camera.aim_offset([0.0, 0.0, 2.0], 1.0, 1.0);
+        assert_close(yaw, 0.4);
+        assert_close(pitch, 0.2);
+    }
+
+    #[test]
+    fn aim_offset_clamped() {
+        let mut camera = Camera::new([0.0, 0.0, 0.0]);
+        camera.set_yaw_pitch(0.4, -0.6);
+        let (yaw, pitch) = camera.aim_offset([0.0, 0.0, 1.0], 0.3, 0.5);
+        assert_close(yaw, 0.3);
+        assert_close(pitch, -0.5);
+    }
+
+    #[test]
+    fn aim_offset_wraps_yaw() {
+        use std::f64::consts::PI;
+
+        let mut camera = Camera::new([0.0, 0.0, 0.0]);
+        camera.set_yaw_pitch(3.0, 0.0);
+        let reference = [(-3.0f64).sin(), 0.0, (-3.0f64).cos()];
+        let (yaw, _) = camera.aim_offset(reference, 1.0, 1.0);
+        assert_close(yaw, 6.0 - 2.0 * PI);
+        camera.set_yaw_pitch(-3.0, 0.0);
+        let reference = [3.0f64.sin(), 0.0, 3.0f64.cos()];
+        let (yaw, _) = camera.aim_offset(reference, 1.0, 1.0);
+        assert_close(yaw, 2.0 * PI - 6.0);
+    }
+
+    #[test]
+    fn fov_tween_eases() {
+        let mut tween = FovTween::new(90.0, 30.0, 1.0);
+        assert!(!tween.is_complete());
+        // Smoothstep is slower than linear at the start.
+        assert!(tween.update(0.25) > 75.0);
+        assert!(!tween.is_complete());
+        assert_close(tween.update(0.25), 60.0);
+        assert_close(tween.update(1.0), 30.0);
+        assert!(tween.is_complete());
+        assert_close(tween.elapsed, 1.0);
+        assert_close(tween.update(1.0), 30.0);
+    }
+
+    #[test]
+    fn fov_tween_zero_duration() {
+        let mut tween = FovTween::new(90.0, 30.0, 0.0);
+        assert!(tween.is_complete());
+        assert_close(tween.fov(), 30.0);
+        assert_close(tween.update(0.1), 30.0);
+    }
+
+    #[test]
+    fn fov_tween_ignores_negative_dt() {
+        let mut tween = FovTween::new(90.0, 30.0, 1.0);
+        assert_close(tween.update(-1.0), 90.0);
+        assert_close(tween.elapsed, 0.0);
+    }
+
+    #[test]
+    fn fov_controller_ignores_negative_dt() {
+        let mut controller = FovController::new(90.0, 1.0);
+        controller.target = 30.0;
+        controller.update(-1.0);
+        assert_close(controller.fov, 90.0);
+    }
 }
@@ -5,11 +5,24 @@
 
 extern crate vecmath;
 extern crate quaternion;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
 pub use camera::{
     Camera,
     CameraPerspective,
+    CameraOrthographic,
+    FovTween,
+    FovController,
+    Frustum,
+    ViewProjection,
     model_view_projection,
+    decompose_view_projection,
+    orthographic_height_for_pixels_per_unit,
 };
+pub use trackball::Trackball;
 
 mod camera;
+mod trackball;
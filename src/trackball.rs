@@ -0,0 +1,137 @@
+//! A trackball camera.
+
+use vecmath::{
+    Vector2,
+    Vector3,
+    vec3_scale,
+    vec3_sub,
+};
+use vecmath::traits::*;
+use quaternion::{self, Quaternion};
+
+use Camera;
+
+/// Rotates a camera around a target using a virtual sphere.
+///
+/// Unlike yaw and pitch angles, the rotation is accumulated as a quaternion,
+/// so it does not suffer from gimbal lock near the poles.
+///
+/// This crate does not depend on the input crate,
+/// so there is no event handler bound to a button.
+/// Instead, feed mouse drags to `drag` from your own event handling.
+#[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trackball<T=f32> {
+    /// The point the camera looks at.
+    pub target: Vector3<T>,
+    /// The distance from the camera to the target.
+    pub distance: T,
+    /// The rotation of the camera relative to the positive z-axis.
+    pub rotation: Quaternion<T>,
+}
+
+impl<T: Float> Trackball<T> {
+    /// Constructs a new trackball looking at a target from negative z.
+    pub fn new(target: Vector3<T>, distance: T) -> Trackball<T> {
+        Trackball {
+            target: target,
+            distance: distance,
+            rotation: quaternion::id(),
+        }
+    }
+
+    /// Rotates the trackball by a drag on the screen.
+    ///
+    /// The points are in normalized device coordinates,
+    /// where the virtual sphere fills the unit circle around the center.
+    /// Dragging rotates the scene along with the cursor.
+    pub fn drag(&mut self, from: Vector2<T>, to: Vector2<T>) {
+        let a = sphere_point(from);
+        let b = sphere_point(to);
+        let q = quaternion::rotation_from_to(a, b);
+        let q = quaternion::mul(self.rotation, quaternion::conj(q));
+        // Normalize to avoid drift when accumulating many small rotations.
+        self.rotation = quaternion::scale(q, T::one() / quaternion::len(q));
+    }
+
+    /// Computes the camera.
+    pub fn camera(&self) -> Camera<T> {
+        let mut camera = Camera::new(self.target);
+        camera.set_rotation(self.rotation);
+        camera.position = vec3_sub(self.target, vec3_scale(camera.forward, self.distance));
+        camera
+    }
+}
+
+/// Projects a screen point onto the virtual sphere, in the local camera space
+/// used by `Camera::set_rotation`.
+///
+/// Points outside the unit circle are projected onto the sphere's rim.
+fn sphere_point<T: Float>(p: Vector2<T>) -> Vector3<T> {
+    let _0: T = Zero::zero();
+    let _1: T = One::one();
+    let d2 = p[0] * p[0] + p[1] * p[1];
+    let [x, y, z] = if d2 <= _1 {
+        [p[0], p[1], (_1 - d2).sqrt()]
+    } else {
+        let inv_len = _1 / d2.sqrt();
+        [p[0] * inv_len, p[1] * inv_len, _0]
+    };
+    // Screen right is local negative x and the viewer is at local negative z.
+    [-x, y, -z]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_6;
+    use vecmath::{vec3_dot, vec3_len, vec3_neg, vec3_normalized};
+
+    const EPSILON: f64 = 1e-6;
+
+    fn assert_vec3_close(a: Vector3<f64>, b: Vector3<f64>) {
+        for i in 0..3 { assert!((a[i] - b[i]).abs() < EPSILON, "{:?} != {:?}", a, b); }
+    }
+
+    #[test]
+    fn drag_right_moves_camera_left() {
+        let mut trackball = Trackball::<f64>::new([0.0, 0.0, 0.0], 5.0);
+        let right = trackball.camera().right;
+        trackball.drag([0.0, 0.0], [0.5, 0.0]);
+        let camera = trackball.camera();
+        assert!(vec3_dot(camera.position, right) < 0.0);
+        assert!((vec3_len(camera.position) - 5.0).abs() < EPSILON);
+        assert_vec3_close(camera.forward, vec3_normalized(vec3_neg(camera.position)));
+    }
+
+    #[test]
+    fn orthogonal_drags_compose() {
+        let mut trackball = Trackball::<f64>::new([0.0, 0.0, 0.0], 5.0);
+        // A drag of 0.5 from the center turns by asin(0.5), which is 30 degrees.
+        trackball.drag([0.0, 0.0], [0.5, 0.0]);
+        trackball.drag([0.0, 0.0], [0.0, 0.5]);
+        let camera = trackball.camera();
+        let mut expected = Camera::new([0.0, 0.0, 0.0]);
+        expected.set_yaw_pitch(-FRAC_PI_6, FRAC_PI_6);
+        assert_vec3_close(camera.forward, expected.forward);
+        assert_vec3_close(camera.up, expected.up);
+        assert_vec3_close(camera.right, expected.right);
+        // No roll, the right direction stays horizontal.
+        assert!(camera.right[1].abs() < EPSILON);
+    }
+
+    #[test]
+    fn drags_back_return_without_drift() {
+        let mut trackball = Trackball::<f64>::new([0.0, 0.0, 0.0], 5.0);
+        for _ in 0..100 {
+            trackball.drag([0.0, 0.0], [0.3, 0.0]);
+            trackball.drag([0.0, 0.0], [0.0, 0.3]);
+            trackball.drag([0.0, 0.0], [0.0, -0.3]);
+            trackball.drag([0.0, 0.0], [-0.3, 0.0]);
+        }
+        let camera = trackball.camera();
+        assert_vec3_close(camera.position, [0.0, 0.0, -5.0]);
+        assert_vec3_close(camera.up, [0.0, 1.0, 0.0]);
+        assert_vec3_close(camera.right, [-1.0, 0.0, 0.0]);
+    }
+}